@@ -0,0 +1,192 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use serde_json::Value;
+use tokio::sync::Semaphore;
+
+/// Configuration for a generic REST-based embedding backend.
+///
+/// `request_template` is a JSON value containing the literal placeholder
+/// `"{{text}}"` somewhere inside it (in a string, nested in an array, or
+/// nested in an object); the placeholder is substituted with the text
+/// being embedded before the request is sent. `response_path` is a
+/// dotted/indexed path (e.g. `"data.0.embedding"` or `"embedding"`) used
+/// to walk the parsed response body down to the embedding array.
+#[derive(Clone, Debug)]
+pub struct RestEmbedderConfig {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub request_template: Value,
+    pub response_path: String,
+    pub dimensions: usize,
+}
+
+/// A pluggable source of text embeddings.
+///
+/// Implementations decide how a piece of text is turned into a vector;
+/// callers only need `embed` and `dimensions`, so the same indexing and
+/// search code works against Ollama, OpenAI, or any custom HTTP backend.
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Embeds a single piece of text.
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// The dimensionality of vectors this embedder produces.
+    fn dimensions(&self) -> usize;
+
+    /// Embeds many texts concurrently, bounded by `concurrency` in-flight
+    /// requests at a time, returning results in the same order as `texts`.
+    async fn embed_chunks(&self, texts: &[String], concurrency: usize) -> Result<Vec<Vec<f32>>> {
+        let semaphore = Semaphore::new(concurrency.max(1));
+        let futures = texts.iter().map(|text| async {
+            let _permit = semaphore
+                .acquire()
+                .await
+                .expect("embedding semaphore should never be closed");
+            self.embed(text).await
+        });
+        try_join_all(futures).await
+    }
+}
+
+/// An [`Embedder`] backed by an arbitrary HTTP embedding endpoint,
+/// configured entirely from a [`RestEmbedderConfig`].
+pub struct RestEmbedder {
+    client: reqwest::Client,
+    config: RestEmbedderConfig,
+}
+
+impl RestEmbedder {
+    pub fn new(client: reqwest::Client, config: RestEmbedderConfig) -> Self {
+        Self { client, config }
+    }
+}
+
+const TEXT_PLACEHOLDER: &str = "{{text}}";
+
+/// Recursively replaces [`TEXT_PLACEHOLDER`] with `text` anywhere it
+/// appears inside a template JSON value.
+fn substitute_text(template: &Value, text: &str) -> Value {
+    match template {
+        Value::String(s) if s == TEXT_PLACEHOLDER => Value::String(text.to_string()),
+        Value::String(s) => Value::String(s.replace(TEXT_PLACEHOLDER, text)),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|v| substitute_text(v, text)).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), substitute_text(v, text)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Walks a dotted/indexed path (e.g. `data.0.embedding`) into a parsed
+/// JSON response, returning the value found at that path.
+fn extract_path<'a>(value: &'a Value, path: &str) -> Result<&'a Value> {
+    let mut current = value;
+    for segment in path.split('.') {
+        current = if let Ok(index) = segment.parse::<usize>() {
+            current
+                .get(index)
+                .ok_or_else(|| anyhow!("response path '{}': no element at index {}", path, index))?
+        } else {
+            current
+                .get(segment)
+                .ok_or_else(|| anyhow!("response path '{}': missing field '{}'", path, segment))?
+        };
+    }
+    Ok(current)
+}
+
+#[async_trait]
+impl Embedder for RestEmbedder {
+    #[tracing::instrument(skip(self, text), fields(embedding.latency_ms = tracing::field::Empty))]
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let started_at = std::time::Instant::now();
+        let body = substitute_text(&self.config.request_template, text);
+
+        let mut request = self.client.post(&self.config.url).json(&body);
+        for (name, value) in &self.config.headers {
+            request = request.header(name, value);
+        }
+
+        let response: Value = request
+            .send()
+            .await
+            .context("embedding request failed")?
+            .json()
+            .await
+            .context("failed to parse embedding response as JSON")?;
+
+        let embedding = extract_path(&response, &self.config.response_path).with_context(|| {
+            format!(
+                "embedding response did not contain a value at '{}'",
+                self.config.response_path
+            )
+        })?;
+
+        let embedding: Vec<f32> = serde_json::from_value(embedding.clone())
+            .context("embedding response path did not contain a numeric array")?;
+
+        tracing::Span::current().record(
+            "embedding.latency_ms",
+            started_at.elapsed().as_millis() as u64,
+        );
+        Ok(embedding)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.config.dimensions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn substitute_text_replaces_top_level_string() {
+        let template = json!({ "prompt": "{{text}}" });
+        assert_eq!(
+            substitute_text(&template, "hello"),
+            json!({ "prompt": "hello" })
+        );
+    }
+
+    #[test]
+    fn substitute_text_replaces_placeholder_nested_in_array() {
+        let template = json!({ "messages": [{ "role": "user", "content": "{{text}}" }] });
+        assert_eq!(
+            substitute_text(&template, "hello"),
+            json!({ "messages": [{ "role": "user", "content": "hello" }] })
+        );
+    }
+
+    #[test]
+    fn substitute_text_leaves_non_matching_values_untouched() {
+        let template = json!({ "model": "bge-m3", "stream": false, "n": 1 });
+        assert_eq!(substitute_text(&template, "hello"), template);
+    }
+
+    #[test]
+    fn extract_path_walks_nested_field_and_index() {
+        let response = json!({ "data": [{ "embedding": [1.0, 2.0, 3.0] }] });
+        let value = extract_path(&response, "data.0.embedding").unwrap();
+        assert_eq!(value, &json!([1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn extract_path_errors_on_missing_field() {
+        let response = json!({ "data": [{}] });
+        assert!(extract_path(&response, "data.0.embedding").is_err());
+    }
+
+    #[test]
+    fn extract_path_errors_on_out_of_range_index() {
+        let response = json!({ "data": [] });
+        assert!(extract_path(&response, "data.0.embedding").is_err());
+    }
+}
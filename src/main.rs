@@ -1,44 +1,101 @@
+mod embedder;
+mod telemetry;
+
+use anyhow::Context;
 use axum::{
-    extract::State,
+    extract::{Path, State},
     http::StatusCode,
     response::{IntoResponse, Json},
-    routing::post,
+    routing::{get, post},
     Router,
 };
 
+use qdrant_client::qdrant::vectors_config::Config;
 use qdrant_client::qdrant::{
-    CreateCollection, Distance, PointStruct, SearchPoints, UpsertPoints, VectorParams, VectorsConfig,
+    point_id::PointIdOptions, points_selector::PointsSelectorOneOf, CreateCollection, DeletePoints,
+    Distance, PointStruct, PointsIdsList, PointsSelector, ScrollPoints, SearchPoints, UpsertPoints,
+    VectorParams, VectorsConfig,
 };
 use qdrant_client::Qdrant;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use qdrant_client::qdrant::vectors_config::Config;
 use serde_json::{Map, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use embedder::{Embedder, RestEmbedder, RestEmbedderConfig};
 
 const COLLECTION_NAME: &str = "normal_server_logs_axum";
+const CACHE_COLLECTION_NAME: &str = "anomaly_cache";
 const EMBEDDING_MODEL: &str = "bge-m3";
-const VECTOR_SIZE: u64 = 1024; 
+const VECTOR_SIZE: u64 = 1024;
+const DEFAULT_EMBEDDING_CONCURRENCY: usize = 8;
+const DEFAULT_CACHE_HIT_THRESHOLD: f32 = 0.97;
+const DEFAULT_CACHE_MAX_SIZE: u64 = 1000;
+const DEFAULT_ANOMALY_THRESHOLD: f32 = 0.70;
 
-#[derive(Clone)]
-struct AppState {
-    qdrant_client: Arc<Qdrant>,
-    http_client: reqwest::Client,
+const DEFAULT_NORMAL_LOGS: [&str; 5] = [
+    "INFO: User 'admin' logged in successfully from IP 192.168.1.10",
+    "INFO: Service 'database-connector' started successfully on port 5432",
+    "DEBUG: Cache cleared for user session 'user123'",
+    "INFO: GET /api/v1/users request processed in 25ms",
+    "INFO: Scheduled backup job 'daily-backup' completed successfully.",
+];
+
+const DEFAULT_KNN_K: u64 = 5;
+
+/// How a log's neighbors in the baseline collection are turned into a
+/// single anomaly score.
+#[derive(Clone, Copy, Debug)]
+enum DecisionStrategy {
+    /// Compare the single closest baseline point's score to the threshold.
+    NearestNeighbor,
+    /// Search `k` neighbors and compare the mean of their scores to the
+    /// threshold, which is more robust to one outlier baseline point.
+    KNearestMean { k: u64 },
 }
 
-#[derive(Serialize)]
-struct OllamaEmbeddingRequest<'a> {
-    model: &'a str,
-    prompt: &'a str,
+/// Reads `ANOMALY_DECISION_STRATEGY` (`"nearest_neighbor"` or
+/// `"k_nearest_mean"`, case-insensitive; defaults to `nearest_neighbor`)
+/// and, for the latter, `ANOMALY_K` (defaults to [`DEFAULT_KNN_K`]).
+fn decision_strategy_from_env() -> DecisionStrategy {
+    match std::env::var("ANOMALY_DECISION_STRATEGY") {
+        Ok(strategy) if strategy.eq_ignore_ascii_case("k_nearest_mean") => {
+            let k = std::env::var("ANOMALY_K")
+                .ok()
+                .and_then(|k| k.parse::<u64>().ok())
+                .filter(|k| *k > 0)
+                .unwrap_or(DEFAULT_KNN_K);
+            DecisionStrategy::KNearestMean { k }
+        }
+        _ => DecisionStrategy::NearestNeighbor,
+    }
 }
 
-#[derive(Deserialize)]
-struct OllamaEmbeddingResponse {
-    embedding: Vec<f32>,
+#[derive(Clone)]
+struct AppState {
+    qdrant_client: Arc<Qdrant>,
+    embedder: Arc<dyn Embedder>,
+    embedding_concurrency: usize,
+    cache_hit_threshold: f32,
+    cache_max_size: u64,
+    cache_next_id: Arc<AtomicU64>,
+    cache_oldest_id: Arc<AtomicU64>,
+    decision_strategy: DecisionStrategy,
+    default_threshold: f32,
+    metrics: telemetry::Metrics,
+    baseline_next_id: Arc<AtomicU64>,
+    baseline_seed_csv_path: Option<String>,
 }
 
 #[derive(Deserialize)]
 struct CheckLogRequest {
     log_entry: String,
+    threshold: Option<f32>,
+}
+
+#[derive(Deserialize)]
+struct CheckLogsRequest {
+    log_entries: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -46,24 +103,81 @@ struct AnomalyResponse {
     is_anomalous: bool,
     score: f32,
     log_entry: String,
+    neighbors: Vec<(String, f32)>,
 }
 
-async fn get_embedding(http_client: &reqwest::Client, log_entry: &str) -> anyhow::Result<Vec<f32>> {
-    let response = http_client
-        .post("http://localhost:11434/api/embeddings")
-        .json(&OllamaEmbeddingRequest {
-            model: EMBEDDING_MODEL,
-            prompt: log_entry,
-        })
-        .send()
-        .await?
-        .json::<OllamaEmbeddingResponse>()
-        .await?;
-    Ok(response.embedding)
+#[derive(Deserialize)]
+struct AddBaselineRequest {
+    logs: Vec<String>,
 }
 
+#[derive(Serialize)]
+struct BaselineEntry {
+    id: u64,
+    log: String,
+}
+
+/// Scrolls every point in `collection_name` and returns the largest numeric
+/// point ID seen, or `None` if the collection is empty. Point IDs are not
+/// necessarily dense once entries have been deleted, so the caller must not
+/// assume `max + 1 == points_count`.
+async fn max_existing_point_id(
+    qdrant_client: &Qdrant,
+    collection_name: &str,
+) -> anyhow::Result<Option<u64>> {
+    let mut max_id = None;
+    let mut offset = None;
+
+    loop {
+        let scroll_result = qdrant_client
+            .scroll(ScrollPoints {
+                collection_name: collection_name.to_string(),
+                with_payload: Some(false.into()),
+                with_vectors: Some(false.into()),
+                limit: Some(1000),
+                offset,
+                ..Default::default()
+            })
+            .await?;
+
+        for point in &scroll_result.result {
+            if let Some(PointIdOptions::Num(id)) =
+                point.id.as_ref().and_then(|id| id.point_id_options.clone())
+            {
+                max_id = Some(max_id.map_or(id, |m: u64| m.max(id)));
+            }
+        }
+
+        offset = scroll_result.next_page_offset;
+        if offset.is_none() {
+            break;
+        }
+    }
+
+    Ok(max_id)
+}
 
 async fn initialize_qdrant_baseline(state: &AppState) -> anyhow::Result<()> {
+    let vector_size = state.embedder.dimensions() as u64;
+
+    let existing_count = match state.qdrant_client.collection_info(COLLECTION_NAME).await {
+        Ok(info) => info.result.and_then(|r| r.points_count).unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    if existing_count > 0 {
+        let next_id = max_existing_point_id(&state.qdrant_client, COLLECTION_NAME)
+            .await?
+            .map_or(0, |max| max + 1);
+        state.baseline_next_id.store(next_id, Ordering::SeqCst);
+        tracing::info!(
+            "Baseline collection '{}' already has {} entries; skipping destructive re-seed.",
+            COLLECTION_NAME,
+            existing_count
+        );
+        return Ok(());
+    }
+
     let _ = state.qdrant_client.delete_collection(COLLECTION_NAME).await;
 
     state
@@ -72,7 +186,7 @@ async fn initialize_qdrant_baseline(state: &AppState) -> anyhow::Result<()> {
             collection_name: COLLECTION_NAME.to_string(),
             vectors_config: Some(VectorsConfig {
                 config: Some(Config::Params(VectorParams {
-                    size: VECTOR_SIZE,
+                    size: vector_size,
                     distance: Distance::Cosine.into(),
                     ..Default::default()
                 })),
@@ -81,18 +195,35 @@ async fn initialize_qdrant_baseline(state: &AppState) -> anyhow::Result<()> {
         })
         .await?;
 
-    let normal_logs = vec![
-        "INFO: User 'admin' logged in successfully from IP 192.168.1.10",
-        "INFO: Service 'database-connector' started successfully on port 5432",
-        "DEBUG: Cache cleared for user session 'user123'",
-        "INFO: GET /api/v1/users request processed in 25ms",
-        "INFO: Scheduled backup job 'daily-backup' completed successfully.",
-    ];
+    let seed_logs: Vec<String> = match &state.baseline_seed_csv_path {
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read baseline seed CSV at '{}'", path))?
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect(),
+        None => DEFAULT_NORMAL_LOGS
+            .iter()
+            .map(|log| log.to_string())
+            .collect(),
+    };
+
+    let vectors = state
+        .embedder
+        .embed_chunks(&seed_logs, state.embedding_concurrency)
+        .await?;
 
     let mut points = Vec::new();
-    for (i, log) in normal_logs.iter().enumerate() {
-        let vector = get_embedding(&state.http_client, log).await?;
-        let payload: Map<String, Value> = serde_json::from_str(&format!(r#"{{"log": "{}"}}"#, log))?;
+    for (i, (log, vector)) in seed_logs.iter().zip(vectors.into_iter()).enumerate() {
+        if vector.len() as u64 != vector_size {
+            anyhow::bail!(
+                "embedder returned a {}-dimensional vector but declared {} dimensions",
+                vector.len(),
+                vector_size
+            );
+        }
+        let payload: Map<String, Value> =
+            serde_json::from_value(serde_json::json!({ "log": log }))?;
         points.push(PointStruct::new(i as u64, vector, payload));
     }
 
@@ -106,17 +237,274 @@ async fn initialize_qdrant_baseline(state: &AppState) -> anyhow::Result<()> {
         })
         .await?;
 
-    tracing::info!("Successfully indexed {} normal log entries.", normal_logs.len());
+    state
+        .baseline_next_id
+        .store(seed_logs.len() as u64, Ordering::SeqCst);
+    tracing::info!(
+        "Successfully indexed {} normal log entries.",
+        seed_logs.len()
+    );
     Ok(())
 }
 
+/// (Re-)creates the semantic result cache collection, discarding any
+/// entries it held. Called at startup, and again whenever the baseline
+/// changes at runtime so stale cache hits can't outlive the baseline
+/// example that invalidated them.
+async fn initialize_cache_collection(state: &AppState) -> anyhow::Result<()> {
+    let vector_size = state.embedder.dimensions() as u64;
+
+    let _ = state
+        .qdrant_client
+        .delete_collection(CACHE_COLLECTION_NAME)
+        .await;
+
+    state
+        .qdrant_client
+        .create_collection(CreateCollection {
+            collection_name: CACHE_COLLECTION_NAME.to_string(),
+            vectors_config: Some(VectorsConfig {
+                config: Some(Config::Params(VectorParams {
+                    size: vector_size,
+                    distance: Distance::Cosine.into(),
+                    ..Default::default()
+                })),
+            }),
+            ..Default::default()
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// The raw, threshold-independent data cached for a previously-scored
+/// vector: its aggregated neighbor score and the neighbor set itself.
+/// `is_anomalous` is deliberately NOT cached — it depends on the
+/// threshold in effect for a given request, so it is re-derived on
+/// every cache hit rather than baked into the stored payload.
+struct CachedScore {
+    score: f32,
+    neighbors: Vec<(String, f32)>,
+}
+
+/// Looks up `vector` in the semantic result cache, returning the cached
+/// score and neighbor set when a near-identical log has already been scored.
+async fn check_cache(state: &AppState, vector: &[f32]) -> anyhow::Result<Option<CachedScore>> {
+    let search_result = state
+        .qdrant_client
+        .search_points(SearchPoints {
+            collection_name: CACHE_COLLECTION_NAME.to_string(),
+            vector: vector.to_vec(),
+            limit: 1,
+            with_payload: Some(true.into()),
+            ..Default::default()
+        })
+        .await?;
+
+    let Some(closest_point) = search_result.result.into_iter().next() else {
+        return Ok(None);
+    };
+
+    if closest_point.score < state.cache_hit_threshold {
+        return Ok(None);
+    }
+
+    let score = closest_point
+        .payload
+        .get("score")
+        .and_then(|v| v.as_double())
+        .map(|s| s as f32)
+        .unwrap_or(closest_point.score);
+
+    let neighbors = closest_point
+        .payload
+        .get("neighbors")
+        .and_then(|v| v.as_list())
+        .map(|list| {
+            list.iter()
+                .filter_map(|entry| {
+                    let pair = entry.as_list()?;
+                    let log = pair.first()?.as_str()?.to_string();
+                    let score = pair.get(1)?.as_double()? as f32;
+                    Some((log, score))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(Some(CachedScore { score, neighbors }))
+}
+
+/// Returns whether inserting a cache entry with this freshly-assigned `id`
+/// pushes the cache over `max_size`, meaning the current oldest surviving
+/// entry must be evicted. IDs are assigned 0, 1, 2, ... in insertion order,
+/// so `id == max_size` is the first insert that overflows the cache.
+fn cache_insert_overflows(id: u64, max_size: u64) -> bool {
+    id >= max_size
+}
+
+/// Upserts `vector` into the semantic result cache with its computed
+/// decision, evicting the oldest entry once `cache_max_size` is exceeded.
+async fn store_in_cache(
+    state: &AppState,
+    vector: Vec<f32>,
+    response: &AnomalyResponse,
+) -> anyhow::Result<()> {
+    let id = state.cache_next_id.fetch_add(1, Ordering::SeqCst);
+
+    let payload: Map<String, Value> = serde_json::from_value(serde_json::json!({
+        "score": response.score,
+        "neighbors": response.neighbors,
+    }))?;
+
+    state
+        .qdrant_client
+        .upsert_points(UpsertPoints {
+            collection_name: CACHE_COLLECTION_NAME.to_string(),
+            points: vec![PointStruct::new(id, vector, payload)],
+            wait: Some(true),
+            ..Default::default()
+        })
+        .await?;
+
+    if cache_insert_overflows(id, state.cache_max_size) {
+        let oldest_id = state.cache_oldest_id.fetch_add(1, Ordering::SeqCst);
+        state
+            .qdrant_client
+            .delete_points(DeletePoints {
+                collection_name: CACHE_COLLECTION_NAME.to_string(),
+                points: Some(PointsSelector {
+                    points_selector_one_of: Some(PointsSelectorOneOf::Points(PointsIdsList {
+                        ids: vec![oldest_id.into()],
+                    })),
+                }),
+                wait: Some(true),
+                ..Default::default()
+            })
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Scores a log entry against the baseline, going through the semantic
+/// cache first so repeated or near-identical entries skip the full search.
+///
+/// A cache hit only reuses the cached score and neighbors; `is_anomalous`
+/// is always re-derived against this call's `threshold` so two identical
+/// logs scored with different thresholds can still get different verdicts.
+async fn evaluate_log_cached(
+    state: &AppState,
+    log_entry: String,
+    vector: Vec<f32>,
+    threshold: f32,
+) -> anyhow::Result<AnomalyResponse> {
+    if let Some(cached) = check_cache(state, &vector).await? {
+        let is_anomalous = is_anomalous(cached.score, &cached.neighbors, threshold);
+        return Ok(AnomalyResponse {
+            is_anomalous,
+            score: cached.score,
+            log_entry,
+            neighbors: cached.neighbors,
+        });
+    }
+
+    let response = evaluate_log(state, log_entry, vector.clone(), threshold).await?;
+    store_in_cache(state, vector, &response).await?;
+    Ok(response)
+}
+
+/// Aggregates `neighbors` into a single anomaly score according to
+/// `strategy`: the closest neighbor's score for [`DecisionStrategy::NearestNeighbor`],
+/// or the mean of all of them for [`DecisionStrategy::KNearestMean`]. An
+/// empty neighbor set scores `0.0`, which [`is_anomalous`] always flags.
+fn aggregate_score(strategy: DecisionStrategy, neighbors: &[(String, f32)]) -> f32 {
+    match strategy {
+        DecisionStrategy::NearestNeighbor => neighbors.first().map(|(_, s)| *s).unwrap_or(0.0),
+        DecisionStrategy::KNearestMean { .. } => {
+            if neighbors.is_empty() {
+                0.0
+            } else {
+                neighbors.iter().map(|(_, s)| s).sum::<f32>() / neighbors.len() as f32
+            }
+        }
+    }
+}
+
+/// A log is anomalous if it has no baseline neighbors at all, or if its
+/// aggregated `score` falls below `threshold`.
+fn is_anomalous(score: f32, neighbors: &[(String, f32)], threshold: f32) -> bool {
+    neighbors.is_empty() || score < threshold
+}
+
+#[tracing::instrument(
+    skip(state, log_entry, vector),
+    fields(
+        qdrant.latency_ms = tracing::field::Empty,
+        score = tracing::field::Empty,
+        is_anomalous = tracing::field::Empty,
+    )
+)]
+async fn evaluate_log(
+    state: &AppState,
+    log_entry: String,
+    vector: Vec<f32>,
+    threshold: f32,
+) -> anyhow::Result<AnomalyResponse> {
+    let limit = match state.decision_strategy {
+        DecisionStrategy::NearestNeighbor => 1,
+        DecisionStrategy::KNearestMean { k } => k,
+    };
+
+    let started_at = std::time::Instant::now();
+    let search_result = state
+        .qdrant_client
+        .search_points(SearchPoints {
+            collection_name: COLLECTION_NAME.to_string(),
+            vector,
+            limit,
+            with_payload: Some(true.into()),
+            ..Default::default()
+        })
+        .await?;
+    tracing::Span::current().record("qdrant.latency_ms", started_at.elapsed().as_millis() as u64);
+
+    let neighbors: Vec<(String, f32)> = search_result
+        .result
+        .iter()
+        .map(|point| {
+            let log = point
+                .payload
+                .get("log")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .unwrap_or_default();
+            (log, point.score)
+        })
+        .collect();
+
+    let score = aggregate_score(state.decision_strategy, &neighbors);
+    let is_anomalous = is_anomalous(score, &neighbors, threshold);
+
+    tracing::Span::current().record("score", score as f64);
+    tracing::Span::current().record("is_anomalous", is_anomalous);
+
+    Ok(AnomalyResponse {
+        is_anomalous,
+        score,
+        log_entry,
+        neighbors,
+    })
+}
+
+#[tracing::instrument(skip(state, payload))]
 async fn check_log_handler(
     State(state): State<AppState>,
     Json(payload): Json<CheckLogRequest>,
 ) -> impl IntoResponse {
-    const ANOMALY_THRESHOLD: f32 = 0.70; 
+    state.metrics.logs_total.add(1, &[]);
 
-    let vector = match get_embedding(&state.http_client, &payload.log_entry).await {
+    let vector = match state.embedder.embed(&payload.log_entry).await {
         Ok(v) => v,
         Err(e) => {
             tracing::error!("Failed to get embedding: {}", e);
@@ -124,62 +512,337 @@ async fn check_log_handler(
         }
     };
 
-    let search_result = match state
+    let threshold = payload.threshold.unwrap_or(state.default_threshold);
+
+    match evaluate_log_cached(&state, payload.log_entry, vector, threshold).await {
+        Ok(response) => {
+            if response.is_anomalous {
+                state.metrics.logs_anomalous.add(1, &[]);
+            }
+            Json(response).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Qdrant search failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Qdrant search failed").into_response()
+        }
+    }
+}
+
+#[tracing::instrument(skip(state, payload))]
+async fn check_logs_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<CheckLogsRequest>,
+) -> impl IntoResponse {
+    state
+        .metrics
+        .logs_total
+        .add(payload.log_entries.len() as u64, &[]);
+
+    let vectors = match state
+        .embedder
+        .embed_chunks(&payload.log_entries, state.embedding_concurrency)
+        .await
+    {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!("Failed to get embeddings: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get embeddings",
+            )
+                .into_response();
+        }
+    };
+
+    let mut responses = Vec::with_capacity(payload.log_entries.len());
+    for (log_entry, vector) in payload.log_entries.into_iter().zip(vectors.into_iter()) {
+        match evaluate_log_cached(&state, log_entry, vector, state.default_threshold).await {
+            Ok(response) => {
+                if response.is_anomalous {
+                    state.metrics.logs_anomalous.add(1, &[]);
+                }
+                responses.push(response);
+            }
+            Err(e) => {
+                tracing::error!("Qdrant search failed: {}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Qdrant search failed").into_response();
+            }
+        }
+    }
+
+    Json(responses).into_response()
+}
+
+async fn add_baseline_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<AddBaselineRequest>,
+) -> impl IntoResponse {
+    let vectors = match state
+        .embedder
+        .embed_chunks(&payload.logs, state.embedding_concurrency)
+        .await
+    {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!("Failed to get embeddings: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get embeddings",
+            )
+                .into_response();
+        }
+    };
+
+    let mut points = Vec::with_capacity(payload.logs.len());
+    let mut entries = Vec::with_capacity(payload.logs.len());
+    for (log, vector) in payload.logs.into_iter().zip(vectors.into_iter()) {
+        let id = state.baseline_next_id.fetch_add(1, Ordering::SeqCst);
+        let entry_payload: Map<String, Value> =
+            match serde_json::from_value(serde_json::json!({ "log": log })) {
+                Ok(p) => p,
+                Err(e) => {
+                    tracing::error!("Failed to build baseline payload: {}", e);
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Failed to build baseline payload",
+                    )
+                        .into_response();
+                }
+            };
+        points.push(PointStruct::new(id, vector, entry_payload));
+        entries.push(BaselineEntry { id, log });
+    }
+
+    if let Err(e) = state
         .qdrant_client
-        .search_points(SearchPoints {
+        .upsert_points(UpsertPoints {
             collection_name: COLLECTION_NAME.to_string(),
-            vector,
-            limit: 1,
-            with_payload: Some(true.into()),
+            points,
+            wait: Some(true),
             ..Default::default()
         })
         .await
     {
-        Ok(res) => res,
-        Err(e) => {
-            tracing::error!("Qdrant search failed: {}", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, "Qdrant search failed").into_response();
-        }
-    };
+        tracing::error!("Failed to upsert baseline entries: {}", e);
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to upsert baseline entries",
+        )
+            .into_response();
+    }
+
+    if let Err(e) = initialize_cache_collection(&state).await {
+        tracing::error!(
+            "Failed to invalidate semantic cache after baseline update: {}",
+            e
+        );
+    }
+
+    Json(entries).into_response()
+}
+
+async fn delete_baseline_handler(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> impl IntoResponse {
+    let result = state
+        .qdrant_client
+        .delete_points(DeletePoints {
+            collection_name: COLLECTION_NAME.to_string(),
+            points: Some(PointsSelector {
+                points_selector_one_of: Some(PointsSelectorOneOf::Points(PointsIdsList {
+                    ids: vec![id.into()],
+                })),
+            }),
+            wait: Some(true),
+            ..Default::default()
+        })
+        .await;
+
+    if let Err(e) = &result {
+        tracing::error!("Failed to delete baseline entry {}: {}", id, e);
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to delete baseline entry",
+        )
+            .into_response();
+    }
+
+    if let Err(e) = initialize_cache_collection(&state).await {
+        tracing::error!(
+            "Failed to invalidate semantic cache after baseline update: {}",
+            e
+        );
+    }
+
+    StatusCode::NO_CONTENT.into_response()
+}
+
+/// Lists every baseline entry, paginating through the full collection via
+/// `next_page_offset` so a curated baseline beyond one scroll page isn't
+/// silently truncated.
+async fn list_baseline_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let mut entries = Vec::new();
+    let mut offset = None;
 
-    let mut score = 0.0;
-    let mut is_anomalous = true;
+    loop {
+        let scroll_result = match state
+            .qdrant_client
+            .scroll(ScrollPoints {
+                collection_name: COLLECTION_NAME.to_string(),
+                with_payload: Some(true.into()),
+                limit: Some(1000),
+                offset,
+                ..Default::default()
+            })
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                tracing::error!("Failed to list baseline entries: {}", e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to list baseline entries",
+                )
+                    .into_response();
+            }
+        };
 
-    if let Some(closest_point) = search_result.result.into_iter().next() {
-        score = closest_point.score;
-        if score >= ANOMALY_THRESHOLD {
-            is_anomalous = false;
+        entries.extend(scroll_result.result.into_iter().filter_map(|point| {
+            let id = match point.id?.point_id_options? {
+                PointIdOptions::Num(id) => id,
+                PointIdOptions::Uuid(_) => return None,
+            };
+            let log = point
+                .payload
+                .get("log")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .unwrap_or_default();
+            Some(BaselineEntry { id, log })
+        }));
+
+        offset = scroll_result.next_page_offset;
+        if offset.is_none() {
+            break;
         }
     }
 
-    Json(AnomalyResponse {
-        is_anomalous,
-        score,
-        log_entry: payload.log_entry,
-    })
-    .into_response()
+    Json(entries).into_response()
 }
 
+const SERVICE_NAME: &str = "anomaly-detection-service";
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::new("info"))
-        .init();
+    telemetry::init_tracing(SERVICE_NAME)?;
+    let metrics = telemetry::init_metrics(SERVICE_NAME)?;
+
+    let embedder: Arc<dyn Embedder> = Arc::new(RestEmbedder::new(
+        reqwest::Client::new(),
+        RestEmbedderConfig {
+            url: "http://localhost:11434/api/embeddings".to_string(),
+            headers: Vec::new(),
+            request_template: serde_json::json!({
+                "model": EMBEDDING_MODEL,
+                "prompt": "{{text}}",
+            }),
+            response_path: "embedding".to_string(),
+            dimensions: VECTOR_SIZE as usize,
+        },
+    ));
 
     let app_state = AppState {
         qdrant_client: Arc::new(Qdrant::from_url("http://localhost:6334").build()?),
-        http_client: reqwest::Client::new(),
+        embedder,
+        embedding_concurrency: DEFAULT_EMBEDDING_CONCURRENCY,
+        cache_hit_threshold: DEFAULT_CACHE_HIT_THRESHOLD,
+        cache_max_size: DEFAULT_CACHE_MAX_SIZE,
+        cache_next_id: Arc::new(AtomicU64::new(0)),
+        cache_oldest_id: Arc::new(AtomicU64::new(0)),
+        decision_strategy: decision_strategy_from_env(),
+        default_threshold: DEFAULT_ANOMALY_THRESHOLD,
+        metrics,
+        baseline_next_id: Arc::new(AtomicU64::new(0)),
+        baseline_seed_csv_path: std::env::var("BASELINE_SEED_CSV_PATH").ok(),
     };
 
     initialize_qdrant_baseline(&app_state).await?;
+    initialize_cache_collection(&app_state).await?;
 
     let app = Router::new()
         .route("/check_log", post(check_log_handler))
+        .route("/check_logs", post(check_logs_handler))
+        .route(
+            "/baseline",
+            get(list_baseline_handler).post(add_baseline_handler),
+        )
+        .route(
+            "/baseline/:id",
+            axum::routing::delete(delete_baseline_handler),
+        )
         .with_state(app_state);
 
     let listener = tokio::net::TcpListener::bind("127.0.0.1:8080").await?;
     tracing::info!("listening on {}", listener.local_addr()?);
     axum::serve(listener, app).await?;
 
+    telemetry::shutdown();
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_insert_overflows_below_max_size() {
+        assert!(!cache_insert_overflows(0, 3));
+        assert!(!cache_insert_overflows(2, 3));
+    }
+
+    #[test]
+    fn cache_insert_overflows_at_and_above_max_size() {
+        assert!(cache_insert_overflows(3, 3));
+        assert!(cache_insert_overflows(4, 3));
+    }
+
+    #[test]
+    fn aggregate_score_nearest_neighbor_uses_closest_only() {
+        let neighbors = vec![("a".to_string(), 0.9), ("b".to_string(), 0.1)];
+        assert_eq!(
+            aggregate_score(DecisionStrategy::NearestNeighbor, &neighbors),
+            0.9
+        );
+    }
+
+    #[test]
+    fn aggregate_score_k_nearest_mean_averages_all_neighbors() {
+        let neighbors = vec![("a".to_string(), 0.9), ("b".to_string(), 0.1)];
+        assert_eq!(
+            aggregate_score(DecisionStrategy::KNearestMean { k: 2 }, &neighbors),
+            0.5
+        );
+    }
+
+    #[test]
+    fn aggregate_score_empty_neighbors_is_zero_for_both_strategies() {
+        assert_eq!(aggregate_score(DecisionStrategy::NearestNeighbor, &[]), 0.0);
+        assert_eq!(
+            aggregate_score(DecisionStrategy::KNearestMean { k: 5 }, &[]),
+            0.0
+        );
+    }
+
+    #[test]
+    fn is_anomalous_flags_empty_neighbors_regardless_of_score() {
+        assert!(is_anomalous(1.0, &[], 0.5));
+    }
+
+    #[test]
+    fn is_anomalous_compares_score_to_threshold() {
+        let neighbors = vec![("a".to_string(), 0.6)];
+        assert!(!is_anomalous(0.6, &neighbors, 0.5));
+        assert!(is_anomalous(0.4, &neighbors, 0.5));
+    }
+}
@@ -0,0 +1,79 @@
+use opentelemetry::metrics::{Counter, Meter};
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::{runtime, trace::Config as TraceConfig, Resource};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+/// Counters tracking how many logs the service has scored, exported
+/// alongside the trace spans so anomaly rate can be charted over time.
+#[derive(Clone)]
+pub struct Metrics {
+    pub logs_total: Counter<u64>,
+    pub logs_anomalous: Counter<u64>,
+}
+
+fn otlp_endpoint() -> String {
+    std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .unwrap_or_else(|_| "http://localhost:4317".to_string())
+}
+
+fn resource(service_name: &str) -> Resource {
+    Resource::new(vec![KeyValue::new(
+        "service.name",
+        service_name.to_string(),
+    )])
+}
+
+/// Installs a tracing subscriber that exports spans to an OTLP collector
+/// (endpoint from `OTEL_EXPORTER_OTLP_ENDPOINT`, default
+/// `http://localhost:4317`) in addition to the existing stdout `fmt` layer.
+pub fn init_tracing(service_name: &str) -> anyhow::Result<()> {
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(otlp_endpoint()),
+        )
+        .with_trace_config(TraceConfig::default().with_resource(resource(service_name)))
+        .install_batch(runtime::Tokio)?;
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::new("info"))
+        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()?;
+
+    Ok(())
+}
+
+/// Installs an OTLP metrics pipeline and returns the counters the service
+/// updates as it scores logs.
+pub fn init_metrics(service_name: &str) -> anyhow::Result<Metrics> {
+    let provider: SdkMeterProvider = opentelemetry_otlp::new_pipeline()
+        .metrics(runtime::Tokio)
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(otlp_endpoint()),
+        )
+        .with_resource(resource(service_name))
+        .build()?;
+
+    global::set_meter_provider(provider);
+
+    let meter: Meter = global::meter(service_name.to_string());
+    Ok(Metrics {
+        logs_total: meter.u64_counter("anomaly_service.logs_total").init(),
+        logs_anomalous: meter.u64_counter("anomaly_service.logs_anomalous").init(),
+    })
+}
+
+/// Flushes and shuts down the OTLP tracer provider; call before the
+/// process exits so buffered spans aren't dropped.
+pub fn shutdown() {
+    global::shutdown_tracer_provider();
+}